@@ -0,0 +1,67 @@
+use std::io::IsTerminal;
+
+use colored::Colorize;
+
+use crate::error::ParseError;
+
+/// Renders a [`ParseError`] against the original source: the offending
+/// line, a caret underlining the exact span, and the error message with
+/// its "expected" list. Colors are skipped when stderr isn't a TTY.
+pub fn render(error: &ParseError, source: &str) -> String {
+    let span = error.span;
+    let line_text = source.lines().nth(span.line - 1).unwrap_or("");
+    let width = (span.end - span.start).max(1);
+    let gutter = format!("{}", span.line).len();
+
+    let caret = format!(
+        "{}{}",
+        " ".repeat(span.column - 1),
+        "^".repeat(width)
+    );
+
+    let header = format!("error: {}", error);
+
+    if std::io::stderr().is_terminal() {
+        format!(
+            "{}\n{:gutter$} |\n{} | {}\n{:gutter$} | {}\n",
+            header.bold().red(),
+            "",
+            span.line,
+            line_text,
+            "",
+            caret.cyan(),
+            gutter = gutter,
+        )
+    } else {
+        format!(
+            "{}\n{:gutter$} |\n{} | {}\n{:gutter$} | {}\n",
+            header,
+            "",
+            span.line,
+            line_text,
+            "",
+            caret,
+            gutter = gutter,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Span;
+
+    #[test]
+    fn render_points_a_caret_at_the_offending_column() {
+        let error = ParseError {
+            span: Span { start: 8, end: 9, line: 1, column: 9 },
+            found: Some("'LBracket'".to_string()),
+            expected: vec![],
+        };
+
+        let rendered = render(&error, "forward [");
+
+        assert!(rendered.contains("1 | forward ["));
+        assert!(rendered.contains(&format!("{}^", " ".repeat(8))));
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+use crate::lexer::TokenKind;
+
+/// A byte range in the source, plus the 1-based line/column of its start,
+/// used to point diagnostics at the offending source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A recoverable parse failure: what was actually found (the offending
+/// token, a short description such as an unmatched `]` or a bad procedure
+/// call, or `None` at end of input), where it was, and the set of token
+/// kinds that would have been accepted at that position.
+#[derive(Debug)]
+pub struct ParseError {
+    pub span: Span,
+    pub found: Option<String>,
+    pub expected: Vec<TokenKind>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let found = self.found.as_deref().unwrap_or("end of input");
+        if self.expected.is_empty() {
+            return write!(f, "{}:{}: unexpected {}", self.span.line, self.span.column, found);
+        }
+        let expected = self
+            .expected
+            .iter()
+            .map(TokenKind::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "{}:{}: unexpected {}, expected one of {}",
+            self.span.line, self.span.column, found, expected
+        )
+    }
+}
+
+/// A failure while evaluating an otherwise well-formed program -- division
+/// by zero, a reference to an undefined `:var`, and the like. Unlike
+/// [`ParseError`] this has no span to point at (the evaluator doesn't carry
+/// source positions through expression values), so it's reported as a
+/// plain message.
+#[derive(Debug)]
+pub struct RuntimeError {
+    pub message: String,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use crate::error::RuntimeError;
+use crate::parser::{ident_name, Comparison, Expression, Op};
+
+/// A single pen-down stroke, in turtle-space coordinates.
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+}
+
+struct Turtle {
+    x: f64,
+    y: f64,
+    heading: f64,
+    pen_down: bool,
+}
+
+impl Turtle {
+    fn new() -> Self {
+        Turtle {
+            x: 0.0,
+            y: 0.0,
+            heading: 0.0,
+            pen_down: true,
+        }
+    }
+}
+
+type Procedure = (Vec<String>, Vec<Expression>);
+
+/// Walks a parsed LOGO program, tracking turtle state and collecting the
+/// line segments drawn by `Forward`/`Back` while the pen is down.
+pub struct Interpreter {
+    turtle: Turtle,
+    segments: Vec<Segment>,
+    procedures: HashMap<String, Procedure>,
+    scopes: Vec<HashMap<String, i32>>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter {
+            turtle: Turtle::new(),
+            segments: Vec::new(),
+            procedures: HashMap::new(),
+            scopes: Vec::new(),
+        }
+    }
+
+    pub fn run(exps: &[Expression]) -> Result<Vec<Segment>, RuntimeError> {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_all(exps)?;
+        Ok(interpreter.segments)
+    }
+
+    fn eval_all(&mut self, exps: &[Expression]) -> Result<(), RuntimeError> {
+        for exp in exps {
+            self.eval(exp)?;
+        }
+        Ok(())
+    }
+
+    fn eval(&mut self, exp: &Expression) -> Result<(), RuntimeError> {
+        match exp {
+            Expression::Forward(dist) => {
+                let dist = self.eval_value(dist)? as f64;
+                self.step(dist);
+            }
+            Expression::Back(dist) => {
+                let dist = self.eval_value(dist)? as f64;
+                self.step(-dist);
+            }
+            Expression::Right(angle) => {
+                self.turtle.heading += self.eval_value(angle)? as f64;
+            }
+            Expression::Left(angle) => {
+                self.turtle.heading -= self.eval_value(angle)? as f64;
+            }
+            Expression::Repeat(count, body) => {
+                let count = self.eval_value(count)?;
+                for _ in 0..count {
+                    self.eval_all(body)?;
+                }
+            }
+            Expression::If(lhs, comparison, rhs, body) => {
+                let lhs = self.eval_value(lhs)?;
+                let rhs = self.eval_value(rhs)?;
+                if holds(comparison, lhs, rhs) {
+                    self.eval_all(body)?;
+                }
+            }
+            Expression::To(ident, params, body) => {
+                let name = ident_name(ident);
+                let params = params.iter().map(ident_name).collect();
+                self.procedures.insert(name, (params, body.clone()));
+            }
+            Expression::Call(ident, args, ..) => {
+                let name = ident_name(ident);
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval_value(arg))
+                    .collect::<Result<Vec<i32>, RuntimeError>>()?;
+                self.call(&name, &args)?;
+            }
+            Expression::Number(_)
+            | Expression::Ident(_)
+            | Expression::Var(_)
+            | Expression::BinOp(..) => {}
+        }
+        Ok(())
+    }
+
+
+    fn call(&mut self, name: &str, args: &[i32]) -> Result<(), RuntimeError> {
+        let (params, body) = match self.procedures.get(name) {
+            Some(procedure) => procedure.clone(),
+            None => panic!("Call to undefined procedure '{}'", name),
+        };
+
+        let mut frame = HashMap::new();
+        for (param, arg) in params.iter().zip(args.iter()) {
+            frame.insert(param.clone(), *arg);
+        }
+        self.scopes.push(frame);
+        let result = self.eval_all(&body);
+        self.scopes.pop();
+        result
+    }
+
+    fn eval_value(&self, exp: &Expression) -> Result<i32, RuntimeError> {
+        match exp {
+            Expression::Number(n) => Ok(*n),
+            Expression::Var(name) => self.lookup(name),
+            Expression::BinOp(op, lhs, rhs) => {
+                let lhs = self.eval_value(lhs)?;
+                let rhs = self.eval_value(rhs)?;
+                match op {
+                    Op::Add => Ok(lhs + rhs),
+                    Op::Sub => Ok(lhs - rhs),
+                    Op::Mul => Ok(lhs * rhs),
+                    Op::Div if rhs == 0 => Err(RuntimeError {
+                        message: format!("division by zero evaluating '{} / {}'", lhs, rhs),
+                    }),
+                    Op::Div => Ok(lhs / rhs),
+                }
+            }
+            _ => panic!("Expected a value, got '{:?}'", exp),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<i32, RuntimeError> {
+        self.scopes
+            .last()
+            .and_then(|frame| frame.get(name))
+            .copied()
+            .ok_or_else(|| RuntimeError {
+                message: format!("reference to undefined variable '{}'", name),
+            })
+    }
+
+    fn step(&mut self, dist: f64) {
+        let radians = self.turtle.heading.to_radians();
+        let x2 = self.turtle.x + dist * radians.sin();
+        let y2 = self.turtle.y - dist * radians.cos();
+
+        if self.turtle.pen_down {
+            self.segments.push(Segment {
+                x1: self.turtle.x,
+                y1: self.turtle.y,
+                x2,
+                y2,
+            });
+        }
+
+        self.turtle.x = x2;
+        self.turtle.y = y2;
+    }
+}
+
+fn holds(comparison: &Comparison, lhs: i32, rhs: i32) -> bool {
+    match comparison {
+        Comparison::Gtr => lhs > rhs,
+        Comparison::Less => lhs < rhs,
+        Comparison::Ge => lhs >= rhs,
+        Comparison::Le => lhs <= rhs,
+        Comparison::Eq => lhs == rhs,
+        Comparison::Ne => lhs != rhs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+
+    #[test]
+    fn if_body_runs_only_when_the_comparison_holds() {
+        let exps = parse(tokenize("if 2 > 1 [ forward 10 ]")).unwrap();
+        let segments = Interpreter::run(&exps).unwrap();
+        assert_eq!(segments.len(), 1);
+
+        let exps = parse(tokenize("if 1 > 2 [ forward 10 ]")).unwrap();
+        let segments = Interpreter::run(&exps).unwrap();
+        assert_eq!(segments.len(), 0);
+    }
+
+    #[test]
+    fn dividing_by_zero_is_a_runtime_error_not_a_panic() {
+        let exps = parse(tokenize("forward 10 / 0")).unwrap();
+        let err = Interpreter::run(&exps).unwrap_err();
+        assert!(err.to_string().contains("division by zero"));
+    }
+
+    #[test]
+    fn referencing_an_undefined_var_is_a_runtime_error_not_a_panic() {
+        let exps = parse(tokenize("forward :nope")).unwrap();
+        let err = Interpreter::run(&exps).unwrap_err();
+        assert!(err.to_string().contains("undefined variable"));
+    }
+}
+
@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use regex::Regex;
+
+use crate::error::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Forward,
+    Back,
+    Right,
+    Left,
+    Repeat,
+    LBracket,
+    RBracket,
+    To,
+    End,
+    Number(i32),
+    Ident(String),
+    Var(String),
+    If,
+    Gtr,
+    Less,
+    Ge,
+    Le,
+    Eq,
+    Ne,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+}
+
+/// The kind of a [`Token`], stripped of its payload, so it can be
+/// collected into the "expected" set a [`ParseError`](crate::error::ParseError)
+/// reports without needing a concrete token to compare against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Forward,
+    Back,
+    Right,
+    Left,
+    Repeat,
+    LBracket,
+    RBracket,
+    To,
+    End,
+    Number,
+    Ident,
+    Var,
+    If,
+    Gtr,
+    Less,
+    Ge,
+    Le,
+    Eq,
+    Ne,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+}
+
+impl From<&Token> for TokenKind {
+    fn from(token: &Token) -> Self {
+        match token {
+            Token::Forward => TokenKind::Forward,
+            Token::Back => TokenKind::Back,
+            Token::Right => TokenKind::Right,
+            Token::Left => TokenKind::Left,
+            Token::Repeat => TokenKind::Repeat,
+            Token::LBracket => TokenKind::LBracket,
+            Token::RBracket => TokenKind::RBracket,
+            Token::To => TokenKind::To,
+            Token::End => TokenKind::End,
+            Token::Number(_) => TokenKind::Number,
+            Token::Ident(_) => TokenKind::Ident,
+            Token::Var(_) => TokenKind::Var,
+            Token::If => TokenKind::If,
+            Token::Gtr => TokenKind::Gtr,
+            Token::Less => TokenKind::Less,
+            Token::Ge => TokenKind::Ge,
+            Token::Le => TokenKind::Le,
+            Token::Eq => TokenKind::Eq,
+            Token::Ne => TokenKind::Ne,
+            Token::Plus => TokenKind::Plus,
+            Token::Minus => TokenKind::Minus,
+            Token::Star => TokenKind::Star,
+            Token::Slash => TokenKind::Slash,
+            Token::LParen => TokenKind::LParen,
+            Token::RParen => TokenKind::RParen,
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            TokenKind::Forward => "forward",
+            TokenKind::Back => "back",
+            TokenKind::Right => "right",
+            TokenKind::Left => "left",
+            TokenKind::Repeat => "repeat",
+            TokenKind::LBracket => "'['",
+            TokenKind::RBracket => "']'",
+            TokenKind::To => "to",
+            TokenKind::End => "end",
+            TokenKind::Number => "a number",
+            TokenKind::Ident => "an identifier",
+            TokenKind::Var => "a :var",
+            TokenKind::If => "if",
+            TokenKind::Gtr => "'>'",
+            TokenKind::Less => "'<'",
+            TokenKind::Ge => "'>='",
+            TokenKind::Le => "'<='",
+            TokenKind::Eq => "'=='",
+            TokenKind::Ne => "'!='",
+            TokenKind::Plus => "'+'",
+            TokenKind::Minus => "'-'",
+            TokenKind::Star => "'*'",
+            TokenKind::Slash => "'/'",
+            TokenKind::LParen => "'('",
+            TokenKind::RParen => "')'",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+/// Tokenizes `source`, recording each token's byte range and 1-based
+/// line/column so parse errors can point back at the exact source text.
+pub fn tokenize(source: &str) -> VecDeque<SpannedToken> {
+    let regex = Regex::new(r":*[a-zA-Z0-9]+|[0-9]+|\[|\]|\(|\)|\+|-|\*|/|(<=|<|>=|>|==|!=|!)").unwrap();
+    let mut tokens = VecDeque::new();
+
+    for m in regex.find_iter(source) {
+        let (line, column) = locate(source, m.start());
+        let span = Span {
+            start: m.start(),
+            end: m.end(),
+            line,
+            column,
+        };
+        let token = match m.as_str() {
+            "forward" => Token::Forward,
+            "back"    => Token::Back,
+            "right"   => Token::Right,
+            "left"    => Token::Left,
+            "repeat"  => Token::Repeat,
+            "["       => Token::LBracket,
+            "]"       => Token::RBracket,
+            "to"      => Token::To,
+            "end"     => Token::End,
+            "if"      => Token::If,
+            ">"       => Token::Gtr,
+            "<"       => Token::Less,
+            ">="      => Token::Ge,
+            "<="      => Token::Le,
+            "=="      => Token::Eq,
+            "!="      => Token::Ne,
+            "+"       => Token::Plus,
+            "-"       => Token::Minus,
+            "*"       => Token::Star,
+            "/"       => Token::Slash,
+            "("       => Token::LParen,
+            ")"       => Token::RParen,
+            text      => match text.parse::<i32>() {
+                Ok(n) => Token::Number(n),
+                Err(_) => {
+                    let string = String::from(text);
+                    match string.chars().next().unwrap() {
+                        ':' => Token::Var(string),
+                        _   => Token::Ident(string)
+                    }
+                }
+            }
+        };
+        tokens.push_back(SpannedToken { token, span });
+    }
+
+    tokens
+}
+
+fn locate(source: &str, offset: usize) -> (usize, usize) {
+    let prefix = &source[..offset];
+    let line = prefix.matches('\n').count() + 1;
+    let column = match prefix.rfind('\n') {
+        Some(newline) => offset - newline,
+        None => offset + 1,
+    };
+    (line, column)
+}
@@ -0,0 +1,378 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::error::{ParseError, Span};
+use crate::lexer::{SpannedToken, Token, TokenKind};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Gtr,
+    Less,
+    Ge,
+    Le,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Forward(Box<Expression>),
+    Back(Box<Expression>),
+    Right(Box<Expression>),
+    Left(Box<Expression>),
+
+    Repeat(Box<Expression>, Vec<Expression>),
+    To(Box<Expression>, Vec<Expression>, Vec<Expression>),
+    Call(Box<Expression>, Vec<Expression>, Span),
+    BinOp(Op, Box<Expression>, Box<Expression>),
+    If(Box<Expression>, Comparison, Box<Expression>, Vec<Expression>),
+
+    Number(i32),
+    Ident(String),
+    Var(String)
+}
+
+/// Extracts the procedure/variable name out of an `Ident`/`Var` node.
+/// Panics on anything else -- only ever called on positions the grammar
+/// already restricts to identifiers.
+pub fn ident_name(exp: &Expression) -> String {
+    match exp {
+        Expression::Ident(name) => name.clone(),
+        Expression::Var(name) => name.clone(),
+        other => panic!("Expected identifier, got '{:?}'", other),
+    }
+}
+
+pub fn parse(tokens: VecDeque<SpannedToken>) -> Result<Vec<Expression>, ParseError> {
+    Parser::new(tokens).parse()
+}
+
+/// Recursive-descent parser. Tracks a running set of the token kinds that
+/// would have been accepted at the current position (`expected`), cleared
+/// whenever a token of one of those kinds is actually consumed, so a failed
+/// parse can report e.g. "expected one of a number, a :var, '('" instead of
+/// a bare panic.
+struct Parser {
+    tokens: VecDeque<SpannedToken>,
+    stack: VecDeque<Token>,
+    expected: HashSet<TokenKind>,
+    last_span: Span,
+}
+
+impl Parser {
+    fn new(tokens: VecDeque<SpannedToken>) -> Self {
+        Parser {
+            tokens,
+            stack: VecDeque::new(),
+            expected: HashSet::new(),
+            last_span: Span { start: 0, end: 0, line: 1, column: 1 },
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Expression>, ParseError> {
+        let exps = self.build()?;
+        match self.stack.pop_back() {
+            Some(Token::LBracket) => Err(self.expected_error(TokenKind::RBracket)),
+            Some(Token::To)       => Err(self.expected_error(TokenKind::End)),
+            _                     => Ok(exps)
+        }
+    }
+
+    /// Parses statements until the block opened by our caller (if any)
+    /// closes. `depth` is `self.stack.len()` as seen by the caller, which
+    /// already pushed its own opening marker before calling us -- so once
+    /// `pop_stack` drops the stack below that depth, the `]`/`end` we just
+    /// consumed was ours to close, and we must return immediately rather
+    /// than keep folding the caller's trailing siblings into our own body.
+    fn build(&mut self) -> Result<Vec<Expression>, ParseError> {
+        let depth = self.stack.len();
+        let mut exps = vec!();
+
+        while !self.tokens.is_empty() {
+            let next = self.advance().unwrap();
+            match next.token {
+                Token::Forward   => exps.push(Expression::Forward(Box::new(self.build_var()?))),
+                Token::Back      => exps.push(Expression::Back(Box::new(self.build_var()?))),
+                Token::Right     => exps.push(Expression::Right(Box::new(self.build_var()?))),
+                Token::Left      => exps.push(Expression::Left(Box::new(self.build_var()?))),
+                Token::Repeat    => exps.push(self.build_repeat()?),
+                Token::If        => exps.push(self.build_if()?),
+                Token::RBracket  => {
+                    self.pop_stack(Token::LBracket, TokenKind::LBracket, TokenKind::RBracket, next.span)?;
+                    if self.stack.len() < depth {
+                        return Ok(exps);
+                    }
+                }
+                Token::To        => exps.push(self.build_to()?),
+                Token::End       => {
+                    self.pop_stack(Token::To, TokenKind::To, TokenKind::End, next.span)?;
+                    if self.stack.len() < depth {
+                        return Ok(exps);
+                    }
+                }
+                Token::Ident(x)  => exps.push(self.build_call(x, next.span)?),
+                _                => return Err(self.error(Some(next)))
+            };
+        }
+
+        Ok(exps)
+    }
+
+    /// Parses a full arithmetic expression (numbers, `:vars`, parenthesised
+    /// subexpressions, `+ - * /`) via shunting-yard, folding operators onto
+    /// an expression stack as soon as precedence allows them to fire.
+    fn build_var(&mut self) -> Result<Expression, ParseError> {
+        let mut output = vec![self.build_operand()?];
+        let mut operators: Vec<Token> = vec![];
+
+        while let Some(op) = self.peek_operator() {
+            self.advance();
+            while let Some(top) = operators.last() {
+                if precedence(top) >= precedence(&op) {
+                    apply_operator(&mut output, operators.pop().unwrap());
+                } else {
+                    break;
+                }
+            }
+            operators.push(op);
+            output.push(self.build_operand()?);
+        }
+
+        while let Some(op) = operators.pop() {
+            apply_operator(&mut output, op);
+        }
+
+        Ok(output.pop().unwrap())
+    }
+
+    fn build_operand(&mut self) -> Result<Expression, ParseError> {
+        self.expect(&[TokenKind::Number, TokenKind::Var, TokenKind::LParen]);
+        match self.advance() {
+            Some(SpannedToken { token: Token::Number(x), .. }) => Ok(Expression::Number(x)),
+            Some(SpannedToken { token: Token::Var(x), .. })    => Ok(Expression::Var(x)),
+            Some(SpannedToken { token: Token::LParen, .. })    => {
+                let inner = self.build_var()?;
+                self.expect(&[TokenKind::RParen]);
+                match self.advance() {
+                    Some(SpannedToken { token: Token::RParen, .. }) => Ok(inner),
+                    found => Err(self.error(found))
+                }
+            }
+            found => Err(self.error(found))
+        }
+    }
+
+    fn peek_operator(&self) -> Option<Token> {
+        match self.tokens.front().map(|st| &st.token) {
+            Some(Token::Plus)  => Some(Token::Plus),
+            Some(Token::Minus) => Some(Token::Minus),
+            Some(Token::Star)  => Some(Token::Star),
+            Some(Token::Slash) => Some(Token::Slash),
+            _                  => None
+        }
+    }
+
+    fn build_repeat(&mut self) -> Result<Expression, ParseError> {
+        let count = Box::new(self.build_var()?);
+        self.stack.push_back(Token::LBracket);
+        self.expect(&[TokenKind::LBracket]);
+        match self.advance() {
+            Some(SpannedToken { token: Token::LBracket, .. }) => Ok(Expression::Repeat(count, self.build()?)),
+            found => Err(self.error(found))
+        }
+    }
+
+    fn build_if(&mut self) -> Result<Expression, ParseError> {
+        let lhs = Box::new(self.build_var()?);
+        let comparison = self.build_comparison()?;
+        let rhs = Box::new(self.build_var()?);
+        self.stack.push_back(Token::LBracket);
+        self.expect(&[TokenKind::LBracket]);
+        match self.advance() {
+            Some(SpannedToken { token: Token::LBracket, .. }) => {
+                Ok(Expression::If(lhs, comparison, rhs, self.build()?))
+            }
+            found => Err(self.error(found))
+        }
+    }
+
+    fn build_comparison(&mut self) -> Result<Comparison, ParseError> {
+        self.expect(&[TokenKind::Gtr, TokenKind::Less, TokenKind::Ge, TokenKind::Le, TokenKind::Eq, TokenKind::Ne]);
+        match self.advance() {
+            Some(SpannedToken { token: Token::Gtr, .. })  => Ok(Comparison::Gtr),
+            Some(SpannedToken { token: Token::Less, .. }) => Ok(Comparison::Less),
+            Some(SpannedToken { token: Token::Ge, .. })   => Ok(Comparison::Ge),
+            Some(SpannedToken { token: Token::Le, .. })   => Ok(Comparison::Le),
+            Some(SpannedToken { token: Token::Eq, .. })   => Ok(Comparison::Eq),
+            Some(SpannedToken { token: Token::Ne, .. })   => Ok(Comparison::Ne),
+            found                                         => Err(self.error(found))
+        }
+    }
+
+    fn build_to(&mut self) -> Result<Expression, ParseError> {
+        let ident = Box::new(self.build_name()?);
+        self.stack.push_back(Token::To);
+        let mut args = vec!();
+        loop {
+            match self.tokens.front().map(|st| &st.token) {
+                Some(Token::Var(x)) => args.push(Expression::Var(x.to_string())),
+                _                   => break
+            };
+            self.advance();
+        }
+        Ok(Expression::To(ident, args, self.build()?))
+    }
+
+    fn build_name(&mut self) -> Result<Expression, ParseError> {
+        self.expect(&[TokenKind::Ident]);
+        match self.advance() {
+            Some(SpannedToken { token: Token::Ident(x), .. }) => Ok(Expression::Ident(x)),
+            found => Err(self.error(found))
+        }
+    }
+
+    fn build_call(&mut self, name: String, span: Span) -> Result<Expression, ParseError> {
+        let mut args = vec!();
+
+        loop {
+            match self.tokens.front().map(|st| &st.token) {
+                Some(Token::Var(_)) | Some(Token::Number(_)) | Some(Token::LParen) => {
+                    args.push(self.build_var()?)
+                }
+                _ => break
+            }
+        }
+
+        Ok(Expression::Call(Box::new(Expression::Ident(name)), args, span))
+    }
+
+    fn pop_stack(&mut self, open: Token, opener: TokenKind, closer: TokenKind, span: Span) -> Result<(), ParseError> {
+        match self.stack.pop_back() {
+            Some(token) if token == open => Ok(()),
+            _                            => Err(ParseError {
+                span,
+                found: Some(format!("{} with no matching {}", closer, opener)),
+                expected: vec![]
+            })
+        }
+    }
+
+    /// Records that `kinds` would all be accepted at the current position.
+    fn expect(&mut self, kinds: &[TokenKind]) {
+        self.expected.extend(kinds.iter().copied());
+    }
+
+    /// Pops the next token. Only clears `expected` when the popped token is
+    /// actually one of the kinds just recorded via `expect` -- a mismatched
+    /// token must leave `expected` intact so the resulting `error()` can
+    /// still report what would have been accepted here.
+    fn advance(&mut self) -> Option<SpannedToken> {
+        let popped = self.tokens.pop_front();
+        if let Some(st) = &popped {
+            self.last_span = st.span;
+            if self.expected.contains(&TokenKind::from(&st.token)) {
+                self.expected.clear();
+            }
+        }
+        popped
+    }
+
+    fn error(&mut self, found: Option<SpannedToken>) -> ParseError {
+        let expected: Vec<TokenKind> = self.expected.drain().collect();
+        match found {
+            Some(st) => ParseError { span: st.span, found: Some(format!("'{:?}'", st.token)), expected },
+            None     => ParseError { span: self.last_span, found: None, expected }
+        }
+    }
+
+    fn expected_error(&mut self, kind: TokenKind) -> ParseError {
+        self.expect(&[kind]);
+        self.error(None)
+    }
+}
+
+fn precedence(op: &Token) -> u8 {
+    match op {
+        Token::Star | Token::Slash => 2,
+        Token::Plus | Token::Minus => 1,
+        _                          => 0
+    }
+}
+
+fn apply_operator(output: &mut Vec<Expression>, op: Token) {
+    let rhs = Box::new(output.pop().expect("Missing right operand"));
+    let lhs = Box::new(output.pop().expect("Missing left operand"));
+    let op = match op {
+        Token::Plus  => Op::Add,
+        Token::Minus => Op::Sub,
+        Token::Star  => Op::Mul,
+        Token::Slash => Op::Div,
+        _            => unreachable!()
+    };
+    output.push(Expression::BinOp(op, lhs, rhs));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    #[test]
+    fn statement_after_a_closed_block_is_a_sibling_not_a_child() {
+        let exps = parse(tokenize("repeat 2 [ forward 10 ] forward 20")).unwrap();
+
+        match &exps[..] {
+            [Expression::Repeat(_, body), Expression::Forward(_)] => {
+                assert_eq!(body.len(), 1);
+            }
+            other => panic!("expected [Repeat, Forward] siblings, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_error_lists_every_kind_expected_at_the_mismatch() {
+        let err = parse(tokenize("forward [")).unwrap_err();
+        assert!(err.expected.contains(&TokenKind::Number));
+        assert!(err.expected.contains(&TokenKind::Var));
+        assert!(err.expected.contains(&TokenKind::LParen));
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        let exps = parse(tokenize("forward 2 + 3 * 4")).unwrap();
+
+        match &exps[..] {
+            [Expression::Forward(dist)] => match &**dist {
+                Expression::BinOp(Op::Add, lhs, rhs) => {
+                    assert!(matches!(**lhs, Expression::Number(2)));
+                    assert!(matches!(**rhs, Expression::BinOp(Op::Mul, _, _)));
+                }
+                other => panic!("expected a top-level Add, got {:?}", other),
+            },
+            other => panic!("expected a single Forward, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn same_precedence_operators_are_left_associative() {
+        let exps = parse(tokenize("forward 10 - 3 - 2")).unwrap();
+
+        match &exps[..] {
+            [Expression::Forward(dist)] => match &**dist {
+                Expression::BinOp(Op::Sub, lhs, rhs) => {
+                    assert!(matches!(**rhs, Expression::Number(2)));
+                    assert!(matches!(**lhs, Expression::BinOp(Op::Sub, _, _)));
+                }
+                other => panic!("expected a top-level Sub, got {:?}", other),
+            },
+            other => panic!("expected a single Forward, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use crate::error::ParseError;
+use crate::parser::{ident_name, Expression};
+
+/// Walks a built AST and checks that every `Call` targets a procedure
+/// registered by some `To` and passes it the right number of arguments,
+/// before a single line gets evaluated.
+pub fn analyze(exps: &[Expression]) -> Result<(), ParseError> {
+    let mut arities = HashMap::new();
+    collect_arities(exps, &mut arities);
+    check_calls(exps, &arities)
+}
+
+fn collect_arities(exps: &[Expression], arities: &mut HashMap<String, usize>) {
+    for exp in exps {
+        match exp {
+            Expression::To(ident, params, body) => {
+                let name = ident_name(ident);
+                arities.insert(name, params.len());
+                collect_arities(body, arities);
+            }
+            Expression::Repeat(_, body) | Expression::If(_, _, _, body) => {
+                collect_arities(body, arities);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_calls(exps: &[Expression], arities: &HashMap<String, usize>) -> Result<(), ParseError> {
+    for exp in exps {
+        match exp {
+            Expression::Call(ident, args, span) => {
+                let name = ident_name(ident);
+                match arities.get(&name) {
+                    None => {
+                        return Err(ParseError {
+                            span: *span,
+                            found: Some(format!("call to undefined procedure '{}'", name)),
+                            expected: vec![],
+                        })
+                    }
+                    Some(arity) if *arity != args.len() => {
+                        return Err(ParseError {
+                            span: *span,
+                            found: Some(format!(
+                                "call to '{}' with {} argument(s), expected {}",
+                                name,
+                                args.len(),
+                                arity
+                            )),
+                            expected: vec![],
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+            Expression::To(_, _, body) => check_calls(body, arities)?,
+            Expression::Repeat(_, body) | Expression::If(_, _, _, body) => {
+                check_calls(body, arities)?
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+
+    #[test]
+    fn call_to_undefined_procedure_is_rejected() {
+        let exps = parse(tokenize("rect 10 20")).unwrap();
+        let err = analyze(&exps).unwrap_err();
+        assert!(err.found.unwrap().contains("undefined procedure"));
+    }
+
+    #[test]
+    fn call_with_wrong_argument_count_is_rejected() {
+        let exps = parse(tokenize("to rect :arg1 :arg2 end rect 10")).unwrap();
+        let err = analyze(&exps).unwrap_err();
+        assert!(err.found.unwrap().contains("expected 2"));
+    }
+
+    #[test]
+    fn call_with_matching_argument_count_is_accepted() {
+        let exps = parse(tokenize("to rect :arg1 :arg2 end rect 10 20")).unwrap();
+        assert!(analyze(&exps).is_ok());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::interpreter::Segment;
+
+const PADDING: f64 = 10.0;
+const STROKE_WIDTH: f64 = 2.0;
+
+/// Renders pen-down segments as a standalone SVG document, sizing the
+/// viewBox to the drawing's bounding box (plus a small padding margin) so
+/// the whole path is visible regardless of where the turtle wandered.
+pub fn render(segments: &[Segment]) -> String {
+    let (min_x, min_y, max_x, max_y) = bounding_box(segments);
+    let width = max_x - min_x + PADDING * 2.0;
+    let height = max_y - min_y + PADDING * 2.0;
+    let offset_x = PADDING - min_x;
+    let offset_y = PADDING - min_y;
+
+    let mut lines = String::new();
+    for segment in segments {
+        lines.push_str(&format!(
+            "  <line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"black\" stroke-width=\"{}\" />\n",
+            segment.x1 + offset_x,
+            segment.y1 + offset_y,
+            segment.x2 + offset_x,
+            segment.y2 + offset_y,
+            STROKE_WIDTH,
+        ));
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {:.2} {:.2}\" width=\"{:.2}\" height=\"{:.2}\">\n{}</svg>\n",
+        width, height, width, height, lines
+    )
+}
+
+fn bounding_box(segments: &[Segment]) -> (f64, f64, f64, f64) {
+    if segments.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+
+    let mut min_x = f64::INFINITY;
+    let mut min_y = f64::INFINITY;
+    let mut max_x = f64::NEG_INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
+
+    for segment in segments {
+        for (x, y) in [(segment.x1, segment.y1), (segment.x2, segment.y2)] {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+
+    (min_x, min_y, max_x, max_y)
+}